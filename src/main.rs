@@ -1,16 +1,35 @@
-use clap::{Parser, ValueEnum};
+// `AppError::Api` wraps `google_sheets4::Error` as-is rather than boxing it;
+// errors are the unhappy path for a CLI tool, so the larger `Result` is fine.
+#![allow(clippy::result_large_err)]
+
+use arrow::array::{ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType as ArrowDataType, Field, Schema as ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use csv::Writer;
 use google_sheets4::Sheets;
+use google_sheets4::api::{BatchUpdateValuesRequest, ValueRange};
 use google_sheets4::hyper_rustls::HttpsConnectorBuilder;
 use google_sheets4::hyper_util::client::legacy::Client;
+use google_sheets4::hyper_util::client::legacy::connect::HttpConnector;
 use google_sheets4::hyper_util::rt::TokioExecutor;
 use google_sheets4::yup_oauth2;
 use google_sheets4::yup_oauth2::ServiceAccountAuthenticator;
-use serde_json::Value;
+use object_store::ObjectStore;
+use object_store::ObjectStoreExt;
+use object_store::aws::AmazonS3Builder;
+use object_store::gcp::GoogleCloudStorageBuilder;
+use object_store::path::Path as ObjectPath;
+use parquet::arrow::ArrowWriter;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Map, Value};
 use thiserror::Error;
 
+use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Error)]
 enum ParseError {
@@ -53,6 +72,49 @@ enum AppError {
 
     #[error(transparent)]
     WriteError(#[from] csv::Error),
+
+    #[error("failed to parse schema file {path}: {source}")]
+    SchemaFile {
+        path: PathBuf,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("schema file has an unrecognized extension: {0}")]
+    UnknownSchemaFormat(PathBuf),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[error("could not parse '{0}' as an A1 range (expected e.g. 'Sheet1!A1:Z100')")]
+    InvalidRange(String),
+
+    #[error(
+        "could not parse '{0}' as an output destination (expected a path, `file://`, `s3://bucket/key` or `gs://bucket/object`)"
+    )]
+    InvalidOutputUri(String),
+
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error("no usable credentials for --auth {auth:?}: {reason}")]
+    NoCredentials {
+        auth: AuthBackend,
+        reason: &'static str,
+    },
+
+    #[error("invalid regex {pattern:?} in schema validator: {source}")]
+    InvalidRegex {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -62,7 +124,47 @@ enum OnError {
     Log,
 }
 
-#[derive(Debug, Clone)]
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    Csv,
+    Json,
+    Ndjson,
+    Parquet,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum InputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ValueInputOption {
+    Raw,
+    UserEntered,
+}
+
+/// Which credential source `build_hub` should use. All three share the same
+/// on-disk token cache (`AuthArgs::token_cache_file`) via yup_oauth2's
+/// `persist_tokens_to_disk`, so repeated invocations don't re-authenticate.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum AuthBackend {
+    ServiceAccount,
+    Oauth,
+    Adc,
+}
+
+impl ValueInputOption {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ValueInputOption::Raw => "RAW",
+            ValueInputOption::UserEntered => "USER_ENTERED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 enum DataType {
     String,
     Integer,
@@ -70,6 +172,115 @@ enum DataType {
     Boolean,
 }
 
+/// On-disk representation of a single validation rule, as written in a
+/// `--schema` file. Converted to a [`Validator`] (which holds a pre-compiled
+/// `Regex` rather than a pattern string) by [`apply_schema_file`], so an
+/// invalid `regex:` pattern fails schema loading once instead of every row.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ValidatorDef {
+    Regex(String),
+    Min(f64),
+    Max(f64),
+    OneOf(Vec<String>),
+    MaxLen(usize),
+}
+
+/// A single declarative validation rule, applied to an already-typed
+/// `DataValue` in `Schema::parse_row`. Built from a [`ValidatorDef`] when a
+/// schema file is loaded.
+#[derive(Debug, Clone)]
+enum Validator {
+    Regex(Regex),
+    Min(f64),
+    Max(f64),
+    OneOf(Vec<String>),
+    MaxLen(usize),
+}
+
+impl TryFrom<ValidatorDef> for Validator {
+    type Error = AppError;
+
+    fn try_from(def: ValidatorDef) -> Result<Self, AppError> {
+        Ok(match def {
+            ValidatorDef::Regex(pattern) => {
+                let re = Regex::new(&pattern).map_err(|source| AppError::InvalidRegex {
+                    pattern: pattern.clone(),
+                    source,
+                })?;
+                Validator::Regex(re)
+            }
+            ValidatorDef::Min(v) => Validator::Min(v),
+            ValidatorDef::Max(v) => Validator::Max(v),
+            ValidatorDef::OneOf(v) => Validator::OneOf(v),
+            ValidatorDef::MaxLen(v) => Validator::MaxLen(v),
+        })
+    }
+}
+
+impl Validator {
+    /// Returns `Ok(())` if `value` satisfies this rule, otherwise a message
+    /// describing the failure for use in `ParseError::ValidationError`.
+    fn check(&self, value: &DataValue) -> Result<(), String> {
+        match (self, value) {
+            (Validator::Regex(re), DataValue::String(s)) => {
+                if re.is_match(s) {
+                    Ok(())
+                } else {
+                    Err(format!("{:?} does not match /{}/", s, re.as_str()))
+                }
+            }
+            (Validator::MaxLen(max), DataValue::String(s)) => {
+                if s.chars().count() <= *max {
+                    Ok(())
+                } else {
+                    Err(format!("{:?} is longer than max_len {}", s, max))
+                }
+            }
+            (Validator::OneOf(options), DataValue::String(s)) => {
+                if options.iter().any(|o| o == s) {
+                    Ok(())
+                } else {
+                    Err(format!("{:?} is not one of {:?}", s, options))
+                }
+            }
+            (Validator::Min(min), DataValue::Integer(v)) => {
+                if (*v as f64) >= *min {
+                    Ok(())
+                } else {
+                    Err(format!("{} is less than min {}", v, min))
+                }
+            }
+            (Validator::Min(min), DataValue::Float(v)) => {
+                if v >= min {
+                    Ok(())
+                } else {
+                    Err(format!("{} is less than min {}", v, min))
+                }
+            }
+            (Validator::Max(max), DataValue::Integer(v)) => {
+                if (*v as f64) <= *max {
+                    Ok(())
+                } else {
+                    Err(format!("{} is greater than max {}", v, max))
+                }
+            }
+            (Validator::Max(max), DataValue::Float(v)) => {
+                if v <= max {
+                    Ok(())
+                } else {
+                    Err(format!("{} is greater than max {}", v, max))
+                }
+            }
+            // A validator that doesn't apply to this value's type is ignored
+            // rather than treated as a failure; `DataType` already guarantees
+            // the shape, so e.g. `min`/`max` on a String column is a schema
+            // authoring mistake, not a per-row error.
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 enum DataValue {
     String(String),
@@ -89,6 +300,221 @@ impl DataValue {
             DataValue::Null => "".to_string(),
         }
     }
+
+    fn to_json_value(&self) -> Value {
+        match self {
+            DataValue::Integer(v) => Value::from(*v),
+            DataValue::Float(v) => Value::from(*v),
+            DataValue::String(v) => Value::String(v.clone()),
+            DataValue::Boolean(v) => Value::Bool(*v),
+            DataValue::Null => Value::Null,
+        }
+    }
+}
+
+fn data_type_to_arrow(data_type: &DataType) -> ArrowDataType {
+    match data_type {
+        DataType::String => ArrowDataType::Utf8,
+        DataType::Integer => ArrowDataType::Int64,
+        DataType::Float => ArrowDataType::Float64,
+        DataType::Boolean => ArrowDataType::Boolean,
+    }
+}
+
+/// Destination for parsed `Record`s, decoupling `Schema::parse_row`'s output
+/// from any particular serialization. `write_header` is always called once
+/// (even for formats with no header row on the wire, like Parquet, which
+/// still need the column names/types to build their schema up front).
+trait RecordSink {
+    fn write_header(&mut self, columns: &[Column]) -> Result<(), AppError>;
+    fn write_record(&mut self, record: &Record) -> Result<(), AppError>;
+    fn finish(self: Box<Self>) -> Result<(), AppError>;
+}
+
+struct CsvSink {
+    writer: Writer<Box<dyn io::Write + Send>>,
+    emit_header_row: bool,
+}
+
+impl RecordSink for CsvSink {
+    fn write_header(&mut self, columns: &[Column]) -> Result<(), AppError> {
+        if self.emit_header_row {
+            self.writer.write_record(columns.iter().map(|c| &c.name))?;
+        }
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &Record) -> Result<(), AppError> {
+        let row: Vec<String> = record.iter().map(|v| v.to_csv_string()).collect();
+        self.writer.write_record(row)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), AppError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+struct JsonSink {
+    writer: Box<dyn io::Write + Send>,
+    columns: Vec<String>,
+    records: Vec<Value>,
+}
+
+impl RecordSink for JsonSink {
+    fn write_header(&mut self, columns: &[Column]) -> Result<(), AppError> {
+        self.columns = columns.iter().map(|c| c.name.clone()).collect();
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &Record) -> Result<(), AppError> {
+        let mut obj = Map::with_capacity(self.columns.len());
+        for (name, value) in self.columns.iter().zip(record.iter()) {
+            obj.insert(name.clone(), value.to_json_value());
+        }
+        self.records.push(Value::Object(obj));
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), AppError> {
+        serde_json::to_writer(&mut self.writer, &self.records)?;
+        Ok(())
+    }
+}
+
+struct NdjsonSink {
+    writer: Box<dyn io::Write + Send>,
+    columns: Vec<String>,
+}
+
+impl RecordSink for NdjsonSink {
+    fn write_header(&mut self, columns: &[Column]) -> Result<(), AppError> {
+        self.columns = columns.iter().map(|c| c.name.clone()).collect();
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &Record) -> Result<(), AppError> {
+        let mut obj = Map::with_capacity(self.columns.len());
+        for (name, value) in self.columns.iter().zip(record.iter()) {
+            obj.insert(name.clone(), value.to_json_value());
+        }
+        serde_json::to_writer(&mut self.writer, &Value::Object(obj))?;
+        writeln!(self.writer)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<(), AppError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+struct ParquetSink {
+    writer: Box<dyn io::Write + Send>,
+    fields: Vec<(String, DataType)>,
+    rows: Vec<Record>,
+}
+
+impl RecordSink for ParquetSink {
+    fn write_header(&mut self, columns: &[Column]) -> Result<(), AppError> {
+        self.fields = columns
+            .iter()
+            .map(|c| (c.name.clone(), c.data_type.clone()))
+            .collect();
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &Record) -> Result<(), AppError> {
+        self.rows.push(record.clone());
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<(), AppError> {
+        let arrow_fields: Vec<Field> = self
+            .fields
+            .iter()
+            .map(|(name, data_type)| Field::new(name, data_type_to_arrow(data_type), true))
+            .collect();
+        let arrow_schema = Arc::new(ArrowSchema::new(arrow_fields));
+
+        let mut columns: Vec<ArrayRef> = Vec::with_capacity(self.fields.len());
+        for (i, (_, data_type)) in self.fields.iter().enumerate() {
+            let array: ArrayRef = match data_type {
+                DataType::String => Arc::new(
+                    self.rows
+                        .iter()
+                        .map(|r| match &r.0[i] {
+                            DataValue::String(s) => Some(s.clone()),
+                            DataValue::Null => None,
+                            other => Some(other.to_csv_string()),
+                        })
+                        .collect::<StringArray>(),
+                ),
+                DataType::Integer => Arc::new(
+                    self.rows
+                        .iter()
+                        .map(|r| match r.0[i] {
+                            DataValue::Integer(v) => Some(v),
+                            _ => None,
+                        })
+                        .collect::<Int64Array>(),
+                ),
+                DataType::Float => Arc::new(
+                    self.rows
+                        .iter()
+                        .map(|r| match r.0[i] {
+                            DataValue::Float(v) => Some(v),
+                            _ => None,
+                        })
+                        .collect::<Float64Array>(),
+                ),
+                DataType::Boolean => Arc::new(
+                    self.rows
+                        .iter()
+                        .map(|r| match r.0[i] {
+                            DataValue::Boolean(v) => Some(v),
+                            _ => None,
+                        })
+                        .collect::<BooleanArray>(),
+                ),
+            };
+            columns.push(array);
+        }
+
+        let batch = RecordBatch::try_new(arrow_schema.clone(), columns)?;
+        let mut writer = ArrowWriter::try_new(self.writer, arrow_schema, None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+fn build_sink(
+    format: OutputFormat,
+    writer: Box<dyn io::Write + Send>,
+    emit_header_row: bool,
+) -> Box<dyn RecordSink> {
+    match format {
+        OutputFormat::Csv => Box::new(CsvSink {
+            writer: Writer::from_writer(writer),
+            emit_header_row,
+        }),
+        OutputFormat::Json => Box::new(JsonSink {
+            writer,
+            columns: vec![],
+            records: vec![],
+        }),
+        OutputFormat::Ndjson => Box::new(NdjsonSink {
+            writer,
+            columns: vec![],
+        }),
+        OutputFormat::Parquet => Box::new(ParquetSink {
+            writer,
+            fields: vec![],
+            rows: vec![],
+        }),
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -104,12 +530,117 @@ struct Column {
     name: String,
     data_type: DataType,
     required: bool,
+    validators: Vec<Validator>,
 }
 
 struct Schema {
     columns: Vec<Column>,
 }
 
+/// On-disk representation of a `--schema` file (YAML or TOML), matched onto
+/// header-derived columns by name.
+#[derive(Debug, Deserialize)]
+struct SchemaFile {
+    columns: Vec<ColumnDef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ColumnDef {
+    name: String,
+    #[serde(rename = "type")]
+    data_type: DataType,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    validators: Vec<ValidatorDef>,
+}
+
+fn load_schema_file(path: &Path) -> Result<SchemaFile, AppError> {
+    let content = fs::read_to_string(path)?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml") | Some("yml") => {
+            serde_yaml::from_str(&content).map_err(|e| AppError::SchemaFile {
+                path: path.to_path_buf(),
+                source: Box::new(e),
+            })
+        }
+        Some("toml") => toml::from_str(&content).map_err(|e| AppError::SchemaFile {
+            path: path.to_path_buf(),
+            source: Box::new(e),
+        }),
+        _ => Err(AppError::UnknownSchemaFormat(path.to_path_buf())),
+    }
+}
+
+/// Overrides `base`'s columns with the types/validators declared in
+/// `schema_file`, matching by column name. Columns named in the file but not
+/// found in `base` (i.e. absent from the sheet range) are returned as
+/// `missing` rather than silently dropped. Each `regex` validator is compiled
+/// here, so a bad pattern fails schema loading instead of every row.
+fn apply_schema_file(
+    mut base: Schema,
+    schema_file: SchemaFile,
+) -> Result<(Schema, Vec<String>), AppError> {
+    let mut missing = Vec::new();
+    for def in schema_file.columns {
+        let validators = def
+            .validators
+            .into_iter()
+            .map(Validator::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        match base.columns.iter_mut().find(|c| c.name == def.name) {
+            Some(col) => {
+                col.data_type = def.data_type;
+                col.required = def.required;
+                col.validators = validators;
+            }
+            None => missing.push(def.name),
+        }
+    }
+    Ok((base, missing))
+}
+
+/// Cell values reach `parse_row` as `Value::String` from the Sheets API but
+/// as native JSON scalars when read back from a local JSON file for `import`;
+/// these helpers accept either representation.
+fn value_as_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn value_as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(s) => s.parse::<i64>().ok(),
+        Value::Number(n) => n.as_i64(),
+        _ => None,
+    }
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::String(s) => s.parse::<f64>().ok(),
+        Value::Number(n) => n.as_f64(),
+        _ => None,
+    }
+}
+
+fn value_as_bool(value: &Value) -> Option<bool> {
+    match value {
+        // Sheets returns boolean cells as `TRUE`/`FALSE` under the default
+        // FORMATTED_VALUE render option, so match case-insensitively rather
+        // than relying on `str::parse::<bool>`, which only accepts lowercase.
+        Value::String(s) => match s.to_ascii_lowercase().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        Value::Bool(b) => Some(*b),
+        _ => None,
+    }
+}
+
 impl Schema {
     fn parse_row(&self, row_index: usize, raw_row: Vec<Value>) -> Result<Record, ParseError> {
         let mut processed = Vec::new();
@@ -130,44 +661,51 @@ impl Schema {
             }
 
             let value = match col.data_type {
-                DataType::String => DataValue::String(raw_val.as_str().unwrap_or("").to_string()),
+                DataType::String => DataValue::String(value_as_string(raw_val)),
                 DataType::Integer => {
-                    let s = raw_val.as_str().unwrap_or("0");
-                    s.parse::<i64>().map(DataValue::Integer).map_err(|_| {
-                        ParseError::TypeMismatch {
+                    value_as_i64(raw_val)
+                        .map(DataValue::Integer)
+                        .ok_or_else(|| ParseError::TypeMismatch {
                             row: row_index,
                             col: i,
                             name: col.name.clone(),
                             expected: "Integer",
-                            actual: s.to_string(),
-                        }
-                    })?
+                            actual: raw_val.to_string(),
+                        })?
                 }
                 DataType::Float => {
-                    let s = raw_val.as_str().unwrap_or("0.0");
-                    s.parse::<f64>().map(DataValue::Float).map_err(|_| {
-                        ParseError::TypeMismatch {
+                    value_as_f64(raw_val)
+                        .map(DataValue::Float)
+                        .ok_or_else(|| ParseError::TypeMismatch {
                             row: row_index,
                             col: i,
                             name: col.name.clone(),
                             expected: "Float",
-                            actual: s.to_string(),
-                        }
-                    })?
+                            actual: raw_val.to_string(),
+                        })?
                 }
                 DataType::Boolean => {
-                    let s = raw_val.as_str().unwrap_or("false");
-                    s.parse::<bool>().map(DataValue::Boolean).map_err(|_| {
-                        ParseError::TypeMismatch {
+                    value_as_bool(raw_val)
+                        .map(DataValue::Boolean)
+                        .ok_or_else(|| ParseError::TypeMismatch {
                             row: row_index,
                             col: i,
                             name: col.name.clone(),
                             expected: "Boolean",
-                            actual: s.to_string(),
-                        }
-                    })?
+                            actual: raw_val.to_string(),
+                        })?
                 }
             };
+
+            for validator in &col.validators {
+                if let Err(message) = validator.check(&value) {
+                    return Err(ParseError::ValidationError {
+                        row: row_index,
+                        col: i,
+                        message,
+                    });
+                }
+            }
             processed.push(value);
         }
         Ok(Record(processed))
@@ -178,6 +716,20 @@ impl Schema {
 #[command(author, version, about, long_about=None)]
 #[command(propagate_version = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Pull a range from a sheet and write it as CSV/JSON/NDJSON/Parquet.
+    Export(ExportArgs),
+    /// Read a local CSV/JSON file and push it into a sheet range.
+    Import(ImportArgs),
+}
+
+#[derive(Args)]
+struct ExportArgs {
     #[clap(short, long)]
     sheet_id: String,
 
@@ -193,8 +745,82 @@ struct Cli {
     #[arg(long, value_enum, default_value_t = OnError::Log)]
     on_error: OnError,
 
+    #[arg(long, value_enum, default_value_t = OutputFormat::Csv)]
+    format: OutputFormat,
+
+    /// Infer each column's DataType (and whether it's required) by sampling
+    /// up to `--infer-sample-rows` rows instead of treating everything as
+    /// String. Columns still present in `--schema` keep their declared type.
+    #[arg(long, default_value_t = false)]
+    infer_types: bool,
+
+    #[arg(long, default_value_t = 100)]
+    infer_sample_rows: usize,
+
+    /// Where to write the output: `-` for stdout, a local path or
+    /// `file://` URI, or an `s3://bucket/key` / `gs://bucket/object` URI.
+    #[clap(short, long, default_value = "-")]
+    output: String,
+
+    #[command(flatten)]
+    auth: AuthArgs,
+
+    /// Path to a YAML or TOML schema file declaring column types and
+    /// validators; overrides header-derived columns by name.
+    #[clap(long)]
+    schema: Option<PathBuf>,
+}
+
+/// Credential options shared by `export` and `import`; see `AuthBackend` for
+/// what each `--auth` choice requires.
+#[derive(Args)]
+struct AuthArgs {
+    #[arg(long, value_enum, default_value_t = AuthBackend::ServiceAccount)]
+    auth: AuthBackend,
+
     #[clap(long)]
     service_account_file: Option<PathBuf>,
+
+    /// OAuth client secret file, required when `--auth oauth`.
+    #[clap(long)]
+    client_secret_file: Option<PathBuf>,
+
+    /// Where OAuth/ADC tokens are cached on disk across invocations.
+    #[clap(long, default_value = "gsheet_token_cache.json")]
+    token_cache_file: PathBuf,
+}
+
+#[derive(Args)]
+struct ImportArgs {
+    #[clap(short, long)]
+    sheet_id: String,
+
+    #[clap(short, long, default_value = "Sheet1!A1:Z100")]
+    range: String,
+
+    /// Local CSV or JSON file to read rows from.
+    #[clap(long)]
+    input: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = InputFormat::Csv)]
+    format: InputFormat,
+
+    #[arg(long, default_value_t = false)]
+    has_header: bool,
+
+    /// Path to a YAML or TOML schema file declaring column types and
+    /// validators; overrides header-derived columns by name.
+    #[clap(long)]
+    schema: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = ValueInputOption::UserEntered)]
+    value_input_option: ValueInputOption,
+
+    #[arg(long, value_enum, default_value_t = OnError::Fail)]
+    on_error: OnError,
+
+    #[command(flatten)]
+    auth: AuthArgs,
 }
 
 fn generate_default_schema(columns: usize) -> Schema {
@@ -203,6 +829,7 @@ fn generate_default_schema(columns: usize) -> Schema {
             name: format!("#{}", c),
             data_type: DataType::String,
             required: false,
+            validators: vec![],
         })
         .collect();
     Schema { columns }
@@ -215,22 +842,163 @@ fn generate_schema(header: &[Value]) -> Schema {
             name: column.to_string().trim_matches('"').to_string(),
             data_type: DataType::String,
             required: false,
+            validators: vec![],
         });
     }
     Schema { columns }
 }
 
-#[tokio::main]
-async fn main() -> Result<(), AppError> {
-    let cli = Cli::parse();
+/// Whether `s` parses as a boolean the way `value_as_bool` does, i.e.
+/// case-insensitively (`TRUE`/`FALSE` as returned by Sheets, not just the
+/// lowercase `true`/`false` that `str::parse::<bool>` accepts).
+fn parses_as_bool(s: &str) -> bool {
+    matches!(s.to_ascii_lowercase().as_str(), "true" | "false")
+}
+
+/// Picks the narrowest `DataType` that parses every non-empty value in
+/// `values`, trying Integer, then Float, then Boolean, and falling back to
+/// String.
+fn infer_column_type(values: &[&Value]) -> DataType {
+    let mut all_int = true;
+    let mut all_float = true;
+    let mut all_bool = true;
+    let mut any_seen = false;
+
+    for value in values {
+        if matches!(value, Value::Null) {
+            continue;
+        }
+        let s = value_as_string(value);
+        if s.is_empty() {
+            continue;
+        }
+        any_seen = true;
+        all_int = all_int && s.parse::<i64>().is_ok();
+        all_float = all_float && s.parse::<f64>().is_ok();
+        all_bool = all_bool && parses_as_bool(&s);
+    }
 
-    let mut wtr = Writer::from_writer(io::stdout());
+    if !any_seen {
+        DataType::String
+    } else if all_int {
+        DataType::Integer
+    } else if all_float {
+        DataType::Float
+    } else if all_bool {
+        DataType::Boolean
+    } else {
+        DataType::String
+    }
+}
+
+/// Whether every non-empty, non-null value in `values` parses as
+/// `data_type`. Used to check a sample-derived verdict against the full
+/// dataset before committing to it.
+fn column_matches_type(values: &[&Value], data_type: &DataType) -> bool {
+    values.iter().all(|value| {
+        if matches!(value, Value::Null) {
+            return true;
+        }
+        let s = value_as_string(value);
+        if s.is_empty() {
+            return true;
+        }
+        match data_type {
+            DataType::String => true,
+            DataType::Integer => s.parse::<i64>().is_ok(),
+            DataType::Float => s.parse::<f64>().is_ok(),
+            DataType::Boolean => parses_as_bool(&s),
+        }
+    })
+}
+
+/// Overrides each column's type/required flag in `schema` by sampling up to
+/// `sample_rows` of `rows` for the type. Column names and positions are left
+/// untouched; only `infer_column_type`'s verdict per column index is applied.
+///
+/// Type inference only ever looks at a sample, so a sample-derived type that
+/// the *full* column doesn't actually support falls back to `String` rather
+/// than rejecting a later row as a `TypeMismatch`. `required`, by contrast,
+/// is derived from a full scan of `rows` (not just the sample): it's safe to
+/// mark a column required if every row's cell is genuinely non-empty, since
+/// that can't cause a later row to be rejected.
+fn infer_schema(mut schema: Schema, rows: &[(usize, Vec<Value>)], sample_rows: usize) -> Schema {
+    let sample = &rows[..rows.len().min(sample_rows)];
+    for (col_idx, col) in schema.columns.iter_mut().enumerate() {
+        let sampled: Vec<&Value> = sample
+            .iter()
+            .map(|(_, row)| row.get(col_idx).unwrap_or(&Value::Null))
+            .collect();
+        let data_type = infer_column_type(&sampled);
+
+        let all_values: Vec<&Value> = rows
+            .iter()
+            .map(|(_, row)| row.get(col_idx).unwrap_or(&Value::Null))
+            .collect();
+        col.data_type = if column_matches_type(&all_values, &data_type) {
+            data_type
+        } else {
+            DataType::String
+        };
+        col.required = all_values.iter().all(|value| {
+            !matches!(value, Value::Null) && !value_as_string(value).is_empty()
+        });
+    }
+    schema
+}
+
+type Hub = Sheets<google_sheets4::hyper_rustls::HttpsConnector<HttpConnector>>;
+
+async fn build_hub(auth_args: AuthArgs) -> Result<Hub, AppError> {
+    let authenticator = match auth_args.auth {
+        AuthBackend::ServiceAccount => {
+            let path = auth_args.service_account_file.ok_or(AppError::NoCredentials {
+                auth: AuthBackend::ServiceAccount,
+                reason: "--service-account-file is required for --auth service-account",
+            })?;
+            let creds = yup_oauth2::read_service_account_key(path).await?;
+            ServiceAccountAuthenticator::builder(creds)
+                .persist_tokens_to_disk(auth_args.token_cache_file)
+                .build()
+                .await?
+        }
+        AuthBackend::Oauth => {
+            let path = auth_args.client_secret_file.ok_or(AppError::NoCredentials {
+                auth: AuthBackend::Oauth,
+                reason: "--client-secret-file is required for --auth oauth",
+            })?;
+            let secret = yup_oauth2::read_application_secret(path).await?;
+            yup_oauth2::InstalledFlowAuthenticator::builder(
+                secret,
+                yup_oauth2::InstalledFlowReturnMethod::HTTPRedirect,
+            )
+            .persist_tokens_to_disk(auth_args.token_cache_file)
+            .build()
+            .await?
+        }
+        AuthBackend::Adc => {
+            let opts = yup_oauth2::ApplicationDefaultCredentialsFlowOpts::default();
+            match yup_oauth2::ApplicationDefaultCredentialsAuthenticator::builder(opts).await {
+                yup_oauth2::authenticator::ApplicationDefaultCredentialsTypes::InstanceMetadata(
+                    builder,
+                ) => {
+                    builder
+                        .persist_tokens_to_disk(auth_args.token_cache_file)
+                        .build()
+                        .await?
+                }
+                yup_oauth2::authenticator::ApplicationDefaultCredentialsTypes::ServiceAccount(
+                    builder,
+                ) => {
+                    builder
+                        .persist_tokens_to_disk(auth_args.token_cache_file)
+                        .build()
+                        .await?
+                }
+            }
+        }
+    };
 
-    let service_account_file = cli
-        .service_account_file
-        .expect("service account file is required");
-    let creds = yup_oauth2::read_service_account_key(service_account_file).await?;
-    let auth = ServiceAccountAuthenticator::builder(creds).build().await?;
     let hub = Sheets::new(
         Client::builder(TokioExecutor::new()).build(
             HttpsConnectorBuilder::new()
@@ -239,12 +1007,189 @@ async fn main() -> Result<(), AppError> {
                 .enable_http1()
                 .build(),
         ),
-        auth,
+        authenticator,
     );
+    Ok(hub)
+}
+
+/// A parsed `Sheet1!A1:Z100`-style A1 range, split so that `import` can
+/// compute per-chunk sub-ranges when batching a large input.
+struct A1Range {
+    sheet_prefix: String,
+    start_col: String,
+    start_row: usize,
+    end_col: String,
+}
+
+fn parse_a1_range(range: &str) -> Option<A1Range> {
+    let (sheet_prefix, cells) = match range.rsplit_once('!') {
+        Some((sheet, cells)) => (format!("{}!", sheet), cells),
+        None => (String::new(), range),
+    };
+    let (start, end) = cells.split_once(':')?;
+    let cell_re = Regex::new(r"^([A-Za-z]+)(\d+)$").ok()?;
+    let start_caps = cell_re.captures(start)?;
+    let end_caps = cell_re.captures(end)?;
+    Some(A1Range {
+        sheet_prefix,
+        start_col: start_caps[1].to_string(),
+        start_row: start_caps[2].parse().ok()?,
+        end_col: end_caps[1].to_string(),
+    })
+}
+
+impl A1Range {
+    fn sub_range(&self, row_offset: usize, num_rows: usize) -> String {
+        let start_row = self.start_row + row_offset;
+        let end_row = start_row + num_rows.saturating_sub(1);
+        format!(
+            "{}{}{}:{}{}",
+            self.sheet_prefix, self.start_col, start_row, self.end_col, end_row
+        )
+    }
+}
+
+/// Rows read back from a local CSV/JSON file for `import`, plus the column
+/// names discovered from a header row or JSON object keys (empty if none).
+fn read_csv_rows(
+    path: &Path,
+    has_header: bool,
+) -> Result<(Vec<String>, Vec<Vec<Value>>), AppError> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .from_path(path)?;
+
+    let headers = if has_header {
+        rdr.headers()?.iter().map(|h| h.to_string()).collect()
+    } else {
+        vec![]
+    };
+
+    let mut rows = Vec::new();
+    for result in rdr.records() {
+        let record = result?;
+        rows.push(record.iter().map(|f| Value::String(f.to_string())).collect());
+    }
+    Ok((headers, rows))
+}
+
+/// Column order comes from the first object's key order, which relies on
+/// `serde_json`'s `preserve_order` feature (enabled in `Cargo.toml`) keeping
+/// `Map` backed by an `IndexMap` rather than a `BTreeMap` — without it, keys
+/// come back alphabetized and rows get written into the wrong positional
+/// slot of the target range.
+fn read_json_rows(path: &Path) -> Result<(Vec<String>, Vec<Vec<Value>>), AppError> {
+    let content = fs::read_to_string(path)?;
+    let records: Vec<Map<String, Value>> = serde_json::from_str(&content)?;
+
+    let columns: Vec<String> = records
+        .first()
+        .map(|r| r.keys().cloned().collect())
+        .unwrap_or_default();
+    let rows = records
+        .into_iter()
+        .map(|mut obj| {
+            columns
+                .iter()
+                .map(|c| obj.remove(c).unwrap_or(Value::Null))
+                .collect()
+        })
+        .collect();
+    Ok((columns, rows))
+}
+
+const IMPORT_BATCH_ROWS: usize = 1000;
+
+/// Where `export` sends its serialized records. Object-store destinations
+/// are buffered in memory via `SharedBuffer` and uploaded in one shot after
+/// the `RecordSink` finishes, since `object_store::ObjectStore::put` takes
+/// the whole payload rather than a streaming `Write`.
+enum OutputDestination {
+    Stdout,
+    File(PathBuf),
+    ObjectStore { uri: String },
+}
+
+/// A string containing `://` that isn't one of the recognized schemes (e.g.
+/// a typo'd `gcs://` or an `http://` URL) is rejected rather than falling
+/// through to `File`, which would otherwise silently write to a local file
+/// literally named after the URI.
+fn parse_output(output: &str) -> Result<OutputDestination, AppError> {
+    if output == "-" {
+        Ok(OutputDestination::Stdout)
+    } else if let Some(rest) = output.strip_prefix("file://") {
+        Ok(OutputDestination::File(PathBuf::from(rest)))
+    } else if output.starts_with("s3://") || output.starts_with("gs://") {
+        Ok(OutputDestination::ObjectStore {
+            uri: output.to_string(),
+        })
+    } else if output.contains("://") {
+        Err(AppError::InvalidOutputUri(output.to_string()))
+    } else {
+        Ok(OutputDestination::File(PathBuf::from(output)))
+    }
+}
+
+#[derive(Clone)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Builds the `object_store` backend for an `s3://bucket/key` or
+/// `gs://bucket/object` URI, reusing the same service-account file already
+/// used to authenticate to Sheets for the GCS case.
+async fn build_object_store(
+    uri: &str,
+    service_account_file: &Option<PathBuf>,
+) -> Result<(Arc<dyn ObjectStore>, ObjectPath), AppError> {
+    if let Some(rest) = uri.strip_prefix("gs://") {
+        let (bucket, object) = rest
+            .split_once('/')
+            .ok_or_else(|| AppError::InvalidOutputUri(uri.to_string()))?;
+        let mut builder = GoogleCloudStorageBuilder::new().with_bucket_name(bucket);
+        if let Some(path) = service_account_file {
+            builder = builder.with_service_account_path(path.to_string_lossy());
+        }
+        let store = builder.build()?;
+        Ok((Arc::new(store), ObjectPath::from(object)))
+    } else if let Some(rest) = uri.strip_prefix("s3://") {
+        let (bucket, key) = rest
+            .split_once('/')
+            .ok_or_else(|| AppError::InvalidOutputUri(uri.to_string()))?;
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()?;
+        Ok((Arc::new(store), ObjectPath::from(key)))
+    } else {
+        Err(AppError::InvalidOutputUri(uri.to_string()))
+    }
+}
+
+async fn run_export(args: ExportArgs) -> Result<(), AppError> {
+    let destination = parse_output(&args.output)?;
+    let shared_buffer = SharedBuffer(Arc::new(Mutex::new(Vec::new())));
+    let writer: Box<dyn io::Write + Send> = match &destination {
+        OutputDestination::Stdout => Box::new(io::stdout()),
+        OutputDestination::File(path) => Box::new(fs::File::create(path)?),
+        OutputDestination::ObjectStore { .. } => Box::new(shared_buffer.clone()),
+    };
+    let mut sink = build_sink(args.format, writer, args.output_header);
+
+    let gcs_service_account_file = args.auth.service_account_file.clone();
+    let hub = build_hub(args.auth).await?;
 
     let result = hub
         .spreadsheets()
-        .values_get(&cli.sheet_id, &cli.range)
+        .values_get(&args.sheet_id, &args.range)
         .doit()
         .await?;
 
@@ -253,25 +1198,34 @@ async fn main() -> Result<(), AppError> {
         let mut iter = values.into_iter().enumerate().peekable();
 
         if let Some((_, header)) = iter.peek() {
-            let schema;
-            if cli.has_header {
+            let mut schema;
+            if args.has_header {
                 schema = generate_schema(header);
                 iter.next();
             } else {
                 schema = generate_default_schema(header.len());
             };
-            if cli.output_header {
-                wtr.write_record(schema.columns.iter().map(|c| &c.name))?;
+            let rows: Vec<(usize, Vec<Value>)> = iter.collect();
+            if args.infer_types {
+                schema = infer_schema(schema, &rows, args.infer_sample_rows);
             }
+            if let Some(schema_path) = &args.schema {
+                let schema_file = load_schema_file(schema_path)?;
+                let missing;
+                (schema, missing) = apply_schema_file(schema, schema_file)?;
+                for name in &missing {
+                    eprintln!(
+                        "schema file declares column '{}' which is absent from the sheet range",
+                        name
+                    );
+                }
+            }
+            sink.write_header(&schema.columns)?;
 
-            for (i, raw_row) in iter {
+            for (i, raw_row) in rows {
                 match schema.parse_row(i, raw_row) {
-                    Ok(record) => {
-                        let csv_row: Vec<String> =
-                            record.iter().map(|v| v.to_csv_string()).collect();
-                        wtr.write_record(csv_row)?;
-                    }
-                    Err(e) => match cli.on_error {
+                    Ok(record) => sink.write_record(&record)?,
+                    Err(e) => match args.on_error {
                         OnError::Fail => return Err(e.into()),
                         OnError::Skip => continue,
                         OnError::Log => eprintln!("{:?}", e),
@@ -284,7 +1238,277 @@ async fn main() -> Result<(), AppError> {
     } else {
         eprintln!("data not found");
     }
-    wtr.flush()?;
+    sink.finish()?;
+
+    if let OutputDestination::ObjectStore { uri } = &destination {
+        let bytes = std::mem::take(&mut *shared_buffer.0.lock().unwrap());
+        let (store, path) = build_object_store(uri, &gcs_service_account_file).await?;
+        store.put(&path, bytes.into()).await?;
+    }
 
     Ok(())
 }
+
+async fn run_import(args: ImportArgs) -> Result<(), AppError> {
+    let (header, raw_rows) = match args.format {
+        InputFormat::Csv => read_csv_rows(&args.input, args.has_header)?,
+        InputFormat::Json => read_json_rows(&args.input)?,
+    };
+
+    let mut schema = if !header.is_empty() {
+        let header: Vec<Value> = header.into_iter().map(Value::String).collect();
+        generate_schema(&header)
+    } else {
+        generate_default_schema(raw_rows.first().map(|r| r.len()).unwrap_or(0))
+    };
+    if let Some(schema_path) = &args.schema {
+        let schema_file = load_schema_file(schema_path)?;
+        let missing;
+        (schema, missing) = apply_schema_file(schema, schema_file)?;
+        for name in &missing {
+            eprintln!(
+                "schema file declares column '{}' which is absent from the input",
+                name
+            );
+        }
+    }
+
+    let mut values = Vec::with_capacity(raw_rows.len());
+    for (i, raw_row) in raw_rows.into_iter().enumerate() {
+        match schema.parse_row(i, raw_row) {
+            Ok(record) => {
+                values.push(record.iter().map(|v| v.to_json_value()).collect::<Vec<_>>())
+            }
+            Err(e) => match args.on_error {
+                OnError::Fail => return Err(e.into()),
+                OnError::Skip => continue,
+                OnError::Log => eprintln!("{:?}", e),
+            },
+        }
+    }
+
+    if values.is_empty() {
+        eprintln!("no rows to import, skipping write");
+        return Ok(());
+    }
+
+    let hub = build_hub(args.auth).await?;
+
+    if values.len() > IMPORT_BATCH_ROWS {
+        let a1 = parse_a1_range(&args.range)
+            .ok_or_else(|| AppError::InvalidRange(args.range.clone()))?;
+        let data: Vec<ValueRange> = values
+            .chunks(IMPORT_BATCH_ROWS)
+            .enumerate()
+            .map(|(chunk_index, chunk)| ValueRange {
+                major_dimension: None,
+                range: Some(a1.sub_range(chunk_index * IMPORT_BATCH_ROWS, chunk.len())),
+                values: Some(chunk.to_vec()),
+            })
+            .collect();
+        let request = BatchUpdateValuesRequest {
+            data: Some(data),
+            value_input_option: Some(args.value_input_option.as_str().to_string()),
+            include_values_in_response: None,
+            response_date_time_render_option: None,
+            response_value_render_option: None,
+        };
+        hub.spreadsheets()
+            .values_batch_update(request, &args.sheet_id)
+            .doit()
+            .await?;
+    } else {
+        let value_range = ValueRange {
+            major_dimension: None,
+            range: Some(args.range.clone()),
+            values: Some(values),
+        };
+        hub.spreadsheets()
+            .values_update(value_range, &args.sheet_id, &args.range)
+            .value_input_option(args.value_input_option.as_str())
+            .doit()
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<(), AppError> {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Export(args) => run_export(args).await,
+        Command::Import(args) => run_import(args).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex_validator_compiles_once_and_matches() {
+        let validator = Validator::try_from(ValidatorDef::Regex(r"^\d+$".to_string())).unwrap();
+        assert!(validator.check(&DataValue::String("123".to_string())).is_ok());
+        assert!(validator.check(&DataValue::String("abc".to_string())).is_err());
+    }
+
+    #[test]
+    fn invalid_regex_pattern_fails_fast() {
+        let err = Validator::try_from(ValidatorDef::Regex("(".to_string())).unwrap_err();
+        assert!(matches!(err, AppError::InvalidRegex { .. }));
+    }
+
+    #[test]
+    fn read_json_rows_preserves_column_order() {
+        let path = std::env::temp_dir().join("gsheet_read_json_rows_preserves_column_order.json");
+        fs::write(
+            &path,
+            r#"[{"zebra": 1, "apple": 2, "mango": 3}, {"zebra": 4, "apple": 5, "mango": 6}]"#,
+        )
+        .unwrap();
+
+        let (columns, rows) = read_json_rows(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(columns, vec!["zebra", "apple", "mango"]);
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::from(1), Value::from(2), Value::from(3)],
+                vec![Value::from(4), Value::from(5), Value::from(6)],
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_a1_range_splits_sheet_prefix_and_cells() {
+        let range = parse_a1_range("Sheet1!A1:Z100").unwrap();
+        assert_eq!(range.sheet_prefix, "Sheet1!");
+        assert_eq!(range.start_col, "A");
+        assert_eq!(range.start_row, 1);
+        assert_eq!(range.end_col, "Z");
+    }
+
+    #[test]
+    fn parse_a1_range_without_sheet_prefix() {
+        let range = parse_a1_range("A1:B2").unwrap();
+        assert_eq!(range.sheet_prefix, "");
+        assert_eq!(range.start_col, "A");
+        assert_eq!(range.start_row, 1);
+        assert_eq!(range.end_col, "B");
+    }
+
+    #[test]
+    fn parse_a1_range_rejects_malformed_input() {
+        assert!(parse_a1_range("not a range").is_none());
+        assert!(parse_a1_range("Sheet1!A1").is_none());
+    }
+
+    #[test]
+    fn a1_range_sub_range_offsets_rows() {
+        let range = parse_a1_range("Sheet1!A1:Z100").unwrap();
+        assert_eq!(range.sub_range(0, 1000), "Sheet1!A1:Z1000");
+        assert_eq!(range.sub_range(1000, 500), "Sheet1!A1001:Z1500");
+    }
+
+    #[test]
+    fn parse_output_recognizes_stdout_file_and_object_store_uris() {
+        assert!(matches!(parse_output("-").unwrap(), OutputDestination::Stdout));
+        assert!(matches!(
+            parse_output("file:///tmp/out.csv").unwrap(),
+            OutputDestination::File(_)
+        ));
+        assert!(matches!(
+            parse_output("out.csv").unwrap(),
+            OutputDestination::File(_)
+        ));
+        assert!(matches!(
+            parse_output("s3://bucket/key").unwrap(),
+            OutputDestination::ObjectStore { .. }
+        ));
+        assert!(matches!(
+            parse_output("gs://bucket/object").unwrap(),
+            OutputDestination::ObjectStore { .. }
+        ));
+    }
+
+    #[test]
+    fn infer_column_type_picks_narrowest_matching_type() {
+        let ints = [Value::from(1), Value::from(2), Value::from(3)];
+        let ints: Vec<&Value> = ints.iter().collect();
+        assert_eq!(infer_column_type(&ints), DataType::Integer);
+
+        let floats = [Value::from(1.5), Value::from(2)];
+        let floats: Vec<&Value> = floats.iter().collect();
+        assert_eq!(infer_column_type(&floats), DataType::Float);
+
+        let bools = [Value::from("true"), Value::from("false")];
+        let bools: Vec<&Value> = bools.iter().collect();
+        assert_eq!(infer_column_type(&bools), DataType::Boolean);
+
+        let mixed = [Value::from("1"), Value::from("not a number")];
+        let mixed: Vec<&Value> = mixed.iter().collect();
+        assert_eq!(infer_column_type(&mixed), DataType::String);
+
+        let blank = Value::from("");
+        let empty: Vec<&Value> = vec![&Value::Null, &blank];
+        assert_eq!(infer_column_type(&empty), DataType::String);
+    }
+
+    #[test]
+    fn infer_schema_demotes_to_string_when_full_dataset_contradicts_sample() {
+        let schema = generate_default_schema(1);
+        // Sampled rows all look like integers, but a later row (outside the
+        // sample) is not. infer_schema must see the contradiction and fall
+        // back to String rather than letting parse_row reject that row.
+        let rows = vec![
+            (0, vec![Value::from(1)]),
+            (1, vec![Value::from(2)]),
+            (2, vec![Value::from("not an int")]),
+        ];
+        let inferred = infer_schema(schema, &rows, 2);
+        assert_eq!(inferred.columns[0].data_type, DataType::String);
+    }
+
+    #[test]
+    fn infer_schema_marks_column_required_only_when_every_row_is_non_empty() {
+        let schema = generate_default_schema(1);
+        let rows = vec![(0, vec![Value::from(1)]), (1, vec![Value::from(2)])];
+        let inferred = infer_schema(schema, &rows, 100);
+        assert_eq!(inferred.columns[0].data_type, DataType::Integer);
+        assert!(inferred.columns[0].required);
+    }
+
+    #[test]
+    fn infer_schema_not_required_when_any_row_is_empty_or_null() {
+        let schema = generate_default_schema(1);
+        let rows = vec![
+            (0, vec![Value::from(1)]),
+            (1, vec![Value::Null]),
+            (2, vec![Value::from(3)]),
+        ];
+        let inferred = infer_schema(schema, &rows, 100);
+        assert!(!inferred.columns[0].required);
+    }
+
+    #[test]
+    fn infer_column_type_detects_sheets_uppercase_booleans() {
+        let values = [Value::from("TRUE"), Value::from("FALSE")];
+        let values: Vec<&Value> = values.iter().collect();
+        assert_eq!(infer_column_type(&values), DataType::Boolean);
+    }
+
+    #[test]
+    fn parse_output_rejects_unrecognized_schemes() {
+        assert!(matches!(
+            parse_output("gcs://bucket/object"),
+            Err(AppError::InvalidOutputUri(_))
+        ));
+        assert!(matches!(
+            parse_output("http://example.com/out.csv"),
+            Err(AppError::InvalidOutputUri(_))
+        ));
+    }
+}